@@ -1,16 +1,242 @@
-use crate::pool::connection::Idle;
 use crate::pool::shared::SharedPool;
-use crate::pool::wait_list::WaitList;
-use crate::{Connection, DefaultRuntime, Runtime};
-use crossbeam_queue::ArrayQueue;
-use std::sync::atomic::AtomicU32;
+use crate::{Connection, Runtime};
+use std::ops::{Deref, DerefMut};
 use std::sync::Arc;
+use std::time::Instant;
 
 mod connection;
+mod events;
 mod options;
 mod shared;
+mod state;
+mod statement_cache;
 mod wait_list;
 
+pub use events::{ConnectionCloseReason, PoolEvent};
+pub use options::PoolOptions;
+pub use state::PoolState;
+
 pub struct Pool<Rt: Runtime, C: Connection<Rt>> {
     shared: Arc<SharedPool<Rt, C>>,
 }
+
+impl<Rt: Runtime, C: Connection<Rt>> Clone for Pool<Rt, C> {
+    fn clone(&self) -> Self {
+        Self { shared: self.shared.clone() }
+    }
+}
+
+impl<Rt: Runtime, C: Connection<Rt>> Pool<Rt, C> {
+    /// Record that `sql` was just prepared against one of this pool's connections, marking it
+    /// hot so that every connection checked out afterwards - idle or freshly established - is
+    /// eagerly primed with it instead of re-preparing it lazily on first use. No-op unless
+    /// [`PoolOptions::statement_cache_capacity`][crate::pool::PoolOptions::statement_cache_capacity]
+    /// is nonzero.
+    pub fn record_prepared_statement(&self, sql: &str) {
+        self.shared.record_prepared_statement(sql);
+    }
+
+    /// The SQL texts of the `n` statements most recently prepared across all of this pool's
+    /// connections, most-recent first. Exposed for introspection; priming a checked-out
+    /// connection with these happens automatically, since prepared statement handles are not
+    /// portable between sessions.
+    pub fn hot_statements(&self, n: usize) -> Vec<std::sync::Arc<str>> {
+        self.shared.hot_statements(n)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<Rt: crate::Async, C: Connection<Rt>> Pool<Rt, C> {
+    /// Retrieve a connection from the pool, establishing a new one if the pool is empty and
+    /// under `max_connections`.
+    ///
+    /// Waits for a connection to become available if the pool is at `max_connections`, or for
+    /// an in-flight connect to finish if the pool is at `max_connecting`.
+    pub async fn acquire(&self) -> crate::Result<PoolConnection<Rt, C>> {
+        self.shared.ensure_maintenance_spawned();
+
+        if self.shared.is_closed() {
+            return Err(crate::Error::PoolClosed);
+        }
+
+        self.shared.record_acquire();
+        let started_at = Instant::now();
+        let deadline = started_at + self.shared.options.connect_timeout;
+
+        loop {
+            if let Some(idle) = self.shared.try_acquire_idle() {
+                self.shared.begin_checkout();
+                let Some(live) = self.shared.validate_idle(idle).await else {
+                    self.shared.cancel_checkout();
+                    continue;
+                };
+                self.shared.record_acquire_wait_time(started_at.elapsed());
+                self.shared.fire_event(crate::pool::PoolEvent::ConnectionCheckedOut(&live.raw));
+                return Ok(PoolConnection { live: Some(live), pool: self.shared.clone() });
+            }
+
+            if self.shared.size() < self.shared.options.max_connections {
+                self.shared.begin_checkout();
+                let live = match self.shared.connect(deadline).await {
+                    Ok(live) => live,
+                    Err(crate::Error::PoolTimedOut) => {
+                        self.shared.cancel_checkout();
+                        self.shared.record_acquire_timed_out();
+                        return Err(crate::Error::PoolTimedOut);
+                    }
+                    Err(e) => {
+                        self.shared.cancel_checkout();
+                        return Err(e);
+                    }
+                };
+                self.shared.record_acquire_wait_time(started_at.elapsed());
+                self.shared.fire_event(crate::pool::PoolEvent::ConnectionCheckedOut(&live.raw));
+                return Ok(PoolConnection { live: Some(live), pool: self.shared.clone() });
+            }
+
+            if Instant::now() >= deadline {
+                self.shared.record_acquire_timed_out();
+                return Err(crate::Error::PoolTimedOut);
+            }
+
+            if !self.shared.waiters.wait_async_until::<Rt>(deadline).await {
+                self.shared.record_acquire_timed_out();
+                return Err(crate::Error::PoolTimedOut);
+            }
+
+            if self.shared.is_closed() {
+                return Err(crate::Error::PoolClosed);
+            }
+        }
+    }
+
+    /// Take a snapshot of the pool's current and cumulative statistics, for exporting to
+    /// whatever metrics system the caller uses.
+    pub fn state(&self) -> PoolState {
+        self.shared.state()
+    }
+
+    /// Shut the pool down gracefully.
+    ///
+    /// [`acquire()`][Self::acquire()] starts failing immediately with
+    /// [`Error::PoolClosed`][crate::Error::PoolClosed] instead of waiting for a connection, and
+    /// every task currently blocked in `acquire()` is woken up to observe the same error. Idle
+    /// connections are dropped immediately. This call then waits for every connection still
+    /// checked out to be returned via its [`PoolConnection`] guard, dropping each one instead of
+    /// recycling it, so it does not resolve until all in-flight work on this pool has finished.
+    pub async fn close(&self) {
+        self.shared.close().await;
+    }
+
+    /// Recycle the pool: bump its internal generation and drop all idle connections, without
+    /// otherwise affecting the pool (new connections are still established as usual).
+    ///
+    /// Connections checked out before this call are closed on release rather than recycled,
+    /// which is useful for dropping every connection after a failover without tearing down the
+    /// `Pool` handle itself.
+    pub async fn clear(&self) {
+        self.shared.clear().await;
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl<Rt: crate::Blocking, C: Connection<Rt>> Pool<Rt, C> {
+    /// Blocking equivalent of [`close()`][Self::close()]. Blocks the current thread until every
+    /// checked-out connection has been returned and dropped.
+    pub fn close_blocking(&self) {
+        self.shared.close_blocking();
+    }
+
+    /// Blocking equivalent of [`clear()`][Self::clear()].
+    pub fn clear_blocking(&self) {
+        self.shared.clear_blocking();
+    }
+
+    /// Blocking equivalent of [`acquire()`][Self::acquire()], for use with the `blocking`
+    /// runtime. Also responsible for spawning the background maintenance monitor thread (see
+    /// [`PoolOptions::maintenance_interval`][crate::pool::PoolOptions::maintenance_interval]) the
+    /// first time it's called on a pool built lazily via
+    /// [`PoolOptions::build()`][crate::pool::PoolOptions::build()].
+    pub fn acquire_blocking(&self) -> crate::Result<PoolConnection<Rt, C>> {
+        self.shared.ensure_maintenance_spawned_blocking();
+
+        if self.shared.is_closed() {
+            return Err(crate::Error::PoolClosed);
+        }
+
+        self.shared.record_acquire();
+        let started_at = Instant::now();
+        let deadline = started_at + self.shared.options.connect_timeout;
+
+        loop {
+            if let Some(idle) = self.shared.try_acquire_idle() {
+                self.shared.begin_checkout();
+                let Some(live) = self.shared.validate_idle_blocking(idle) else {
+                    self.shared.cancel_checkout();
+                    continue;
+                };
+                self.shared.record_acquire_wait_time(started_at.elapsed());
+                self.shared.fire_event(crate::pool::PoolEvent::ConnectionCheckedOut(&live.raw));
+                return Ok(PoolConnection { live: Some(live), pool: self.shared.clone() });
+            }
+
+            if self.shared.size() < self.shared.options.max_connections {
+                self.shared.begin_checkout();
+                let live = match self.shared.connect_blocking() {
+                    Ok(live) => live,
+                    Err(crate::Error::PoolTimedOut) => {
+                        self.shared.cancel_checkout();
+                        self.shared.record_acquire_timed_out();
+                        return Err(crate::Error::PoolTimedOut);
+                    }
+                    Err(e) => {
+                        self.shared.cancel_checkout();
+                        return Err(e);
+                    }
+                };
+                self.shared.record_acquire_wait_time(started_at.elapsed());
+                self.shared.fire_event(crate::pool::PoolEvent::ConnectionCheckedOut(&live.raw));
+                return Ok(PoolConnection { live: Some(live), pool: self.shared.clone() });
+            }
+
+            if Instant::now() >= deadline {
+                self.shared.record_acquire_timed_out();
+                return Err(crate::Error::PoolTimedOut);
+            }
+
+            self.shared.waiters.wait_blocking(deadline);
+
+            if self.shared.is_closed() {
+                return Err(crate::Error::PoolClosed);
+            }
+        }
+    }
+}
+
+/// A connection checked out of a [`Pool`]. Returned to the pool's idle queue on drop.
+pub struct PoolConnection<Rt: Runtime, C: Connection<Rt>> {
+    live: Option<crate::pool::connection::Live<Rt, C>>,
+    pool: Arc<SharedPool<Rt, C>>,
+}
+
+impl<Rt: Runtime, C: Connection<Rt>> Deref for PoolConnection<Rt, C> {
+    type Target = C;
+
+    fn deref(&self) -> &C {
+        &self.live.as_ref().expect("PoolConnection used after release").raw
+    }
+}
+
+impl<Rt: Runtime, C: Connection<Rt>> DerefMut for PoolConnection<Rt, C> {
+    fn deref_mut(&mut self) -> &mut C {
+        &mut self.live.as_mut().expect("PoolConnection used after release").raw
+    }
+}
+
+impl<Rt: Runtime, C: Connection<Rt>> Drop for PoolConnection<Rt, C> {
+    fn drop(&mut self) {
+        if let Some(live) = self.live.take() {
+            self.pool.release(live);
+        }
+    }
+}
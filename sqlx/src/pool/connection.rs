@@ -0,0 +1,39 @@
+use crate::{Connection, Runtime};
+use std::time::Instant;
+
+/// A connection that is or was checked out of the pool, tracked alongside the
+/// bookkeeping the pool needs to decide when to retire it.
+pub(crate) struct Live<Rt: Runtime, C: Connection<Rt>> {
+    pub(crate) raw: C,
+    pub(crate) created_at: Instant,
+    /// The pool's generation (see [`Pool::clear()`][super::Pool::clear()]) at the time this
+    /// connection was established. Connections from an older generation than the pool's
+    /// current one are closed on release instead of being recycled.
+    pub(crate) generation: u32,
+    _rt: std::marker::PhantomData<Rt>,
+}
+
+impl<Rt: Runtime, C: Connection<Rt>> Live<Rt, C> {
+    pub(crate) fn new(raw: C, generation: u32) -> Self {
+        Self { raw, created_at: Instant::now(), generation, _rt: std::marker::PhantomData }
+    }
+
+    /// Wrap this connection back up as idle, stamping the time it was returned.
+    pub(crate) fn into_idle(self) -> Idle<Rt, C> {
+        Idle { live: self, returned_at: Instant::now() }
+    }
+}
+
+/// A connection managed by the pool that is currently idle, i.e. sitting in the
+/// idle queue waiting to be handed out by [`acquire`][super::Pool::acquire].
+pub(crate) struct Idle<Rt: Runtime, C: Connection<Rt>> {
+    pub(crate) live: Live<Rt, C>,
+    pub(crate) returned_at: Instant,
+}
+
+impl<Rt: Runtime, C: Connection<Rt>> Idle<Rt, C> {
+    /// How long this connection has been sitting idle in the pool.
+    pub(crate) fn idle_for(&self, since: Instant) -> std::time::Duration {
+        since.saturating_duration_since(self.returned_at)
+    }
+}
@@ -0,0 +1,60 @@
+use crate::{Connection, Runtime};
+
+/// A pool-level lifecycle event, fired by [`SharedPool`][super::shared::SharedPool] at the
+/// various points where a connection changes hands or the pool itself changes state.
+///
+/// Subscribe via [`PoolOptions::on_event`][super::PoolOptions::on_event] to wire the pool into
+/// `tracing`, metrics, or similar, without having to fork `sqlx` to add instrumentation.
+pub enum PoolEvent<'a, Rt: Runtime, C: Connection<Rt>> {
+    /// A new connection was successfully established and added to the pool.
+    ConnectionEstablished(&'a C),
+
+    /// A connection owned by the pool was closed and will not be reused.
+    ConnectionClosed {
+        /// The connection being closed.
+        connection: &'a C,
+        /// Why it was closed.
+        reason: ConnectionCloseReason,
+    },
+
+    /// A connection was handed out to an [`acquire()`][super::Pool::acquire()] caller.
+    ConnectionCheckedOut(&'a C),
+
+    /// A connection was returned to the pool by dropping its [`PoolConnection`][super::PoolConnection] guard.
+    /// Also fired when a connection the pool established on its own (up to
+    /// [`PoolOptions::min_connections`][super::PoolOptions::min_connections]) is added to the
+    /// idle queue.
+    ConnectionCheckedIn(&'a C),
+
+    /// [`Pool::clear()`][super::Pool::clear()] was called; all idle connections were dropped
+    /// and checked-out connections will be closed on return instead of reused.
+    PoolCleared,
+
+    /// [`Pool::close()`][super::Pool::close()] was called; the pool will accept no further
+    /// acquires and is draining its remaining connections.
+    PoolClosed,
+}
+
+/// Why a connection owned by the pool was closed rather than returned to the idle queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionCloseReason {
+    /// The connection had been idle for longer than [`PoolOptions::idle_timeout`][super::PoolOptions::idle_timeout].
+    IdleTimeout,
+
+    /// The connection had been alive for longer than [`PoolOptions::max_lifetime`][super::PoolOptions::max_lifetime].
+    MaxLifetime,
+
+    /// [`test_before_acquire`][super::PoolOptions::test_before_acquire], the
+    /// [`before_acquire`][super::PoolOptions::before_acquire] hook or the
+    /// [`after_release`][super::PoolOptions::after_release] hook determined the connection was
+    /// no longer usable, or the idle queue had no room for it on release.
+    Broken,
+
+    /// [`Pool::close()`][super::Pool::close()] was called while this connection was idle or
+    /// checked out.
+    PoolClosed,
+
+    /// [`Pool::clear()`][super::Pool::clear()] was called while this connection was idle or
+    /// checked out from an older generation. Unlike `PoolClosed`, the pool itself is still open.
+    Cleared,
+}
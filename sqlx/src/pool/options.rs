@@ -1,3 +1,4 @@
+use crate::pool::events::PoolEvent;
 use crate::pool::shared::SharedPool;
 use crate::pool::Pool;
 use crate::{Connect, ConnectOptions, Connection, Runtime};
@@ -10,15 +11,23 @@ use std::time::{Duration, Instant};
 pub struct PoolOptions<Rt: Runtime, C: Connection<Rt>> {
     // general options
     pub(crate) max_connections: u32,
+    pub(crate) max_connecting: u32,
     pub(crate) connect_timeout: Duration,
     pub(crate) min_connections: u32,
     pub(crate) max_lifetime: Option<Duration>,
     pub(crate) idle_timeout: Option<Duration>,
     pub(crate) test_before_acquire: bool,
+    pub(crate) statement_cache_capacity: usize,
+    pub(crate) maintenance_interval: Option<Duration>,
 
     // callback functions (any runtime)
     pub(crate) after_release: Option<Box<dyn Fn(&mut C) -> bool + 'static + Send + Sync>>,
 
+    /// Fired at pool lifecycle decision points (connect, checkout, checkin, close, ...).
+    /// `None` by default, so subscribing costs nothing until a caller opts in.
+    pub(crate) on_event:
+        Option<Box<dyn Fn(PoolEvent<'_, Rt, C>) + 'static + Send + Sync>>,
+
     // callback functions (async)
     #[cfg(feature = "async")]
     pub(crate) after_connect_async: Option<
@@ -33,7 +42,7 @@ pub struct PoolOptions<Rt: Runtime, C: Connection<Rt>> {
     #[cfg(feature = "async")]
     pub(crate) before_acquire_async: Option<
         Box<
-            dyn Fn(&mut C) -> futures_util::BoxFuture<'_, crate::Result<()>>
+            dyn Fn(&mut C) -> futures_util::BoxFuture<'_, crate::Result<bool>>
                 + Send
                 + Sync
                 + 'static,
@@ -46,7 +55,7 @@ pub struct PoolOptions<Rt: Runtime, C: Connection<Rt>> {
         Option<Box<dyn Fn(&mut C) -> crate::Result<()> + Send + Sync + 'static>>,
     #[cfg(feature = "blocking")]
     pub(crate) before_acquire_blocking:
-        Option<Box<dyn Fn(&mut C) -> crate::Result<()> + Send + Sync + 'static>>,
+        Option<Box<dyn Fn(&mut C) -> crate::Result<bool> + Send + Sync + 'static>>,
 
     // to satisfy the orphan type params check
     _rt: PhantomData<Rt>,
@@ -66,11 +75,15 @@ impl<Rt: Runtime, C: Connection<Rt>> PoolOptions<Rt, C> {
         Self {
             min_connections: 0,
             max_connections: 10,
+            max_connecting: 2,
             connect_timeout: Duration::from_secs(30),
             idle_timeout: Some(Duration::from_secs(10 * 60)),
             max_lifetime: Some(Duration::from_secs(30 * 60)),
             test_before_acquire: true,
+            statement_cache_capacity: 0,
+            maintenance_interval: Some(Duration::from_millis(500)),
             after_release: None,
+            on_event: None,
             #[cfg(feature = "async")]
             after_connect_async: None,
             #[cfg(feature = "async")]
@@ -97,6 +110,20 @@ impl<Rt: Runtime, C: Connection<Rt>> PoolOptions<Rt, C> {
         self
     }
 
+    /// Set the maximum number of connections that may be in the process of being established
+    /// at any given time.
+    ///
+    /// Without this limit, a burst of acquirers hitting an empty pool (e.g. on startup, or
+    /// after a stall) would each try to dial the database concurrently, which can itself
+    /// overwhelm the server. Additional acquirers beyond this limit simply wait for one of the
+    /// in-flight connection attempts to finish (successfully or not) before they try again.
+    ///
+    /// Defaults to 2.
+    pub fn max_connecting(mut self, max: u32) -> Self {
+        self.max_connecting = max;
+        self
+    }
+
     /// Set the amount of time to attempt connecting to the database.
     ///
     /// If this timeout elapses, [`Pool::acquire`] will return an error.
@@ -143,6 +170,40 @@ impl<Rt: Runtime, C: Connection<Rt>> PoolOptions<Rt, C> {
         self
     }
 
+    /// Opt in to a pool-wide cache of which SQL statements are hot, shared across all
+    /// connections, so a newly-checked-out connection can eagerly re-prepare them instead of
+    /// re-preparing lazily on first use.
+    ///
+    /// Only meaningful for connections whose [`Database`][crate::database::Database] implements
+    /// [`HasStatementCache`][crate::database::HasStatementCache]; set to 0 (the default) to
+    /// disable.
+    pub fn statement_cache_capacity(mut self, capacity: usize) -> Self {
+        self.statement_cache_capacity = capacity;
+        self
+    }
+
+    /// Set how often the pool's background monitor wakes up to perform housekeeping: reaping
+    /// connections that have exceeded [`idle_timeout`][Self::idle_timeout] or
+    /// [`max_lifetime`][Self::max_lifetime], and eagerly re-establishing connections to restore
+    /// [`min_connections`][Self::min_connections] (subject to
+    /// [`max_connecting`][Self::max_connecting]).
+    ///
+    /// This monitor is the only place idle/lifetime limits are enforced:
+    /// [`Pool::acquire()`][super::Pool::acquire()] does not check them when it hands out an idle
+    /// connection. Without it, an idle pool keeps its connections past the configured timeouts -
+    /// something that matters for the usage-based database server billing scenarios the
+    /// [`idle_timeout`][Self::idle_timeout] docs call out.
+    ///
+    /// Set to `None` to disable the background monitor entirely. Defaults to 500 milliseconds,
+    /// matching the interval MongoDB's connection pool uses for the same purpose.
+    pub fn maintenance_interval(mut self, interval: impl Into<Option<Duration>>) -> Self {
+        self.maintenance_interval = interval.into();
+        self
+    }
+
+    /// Run a check on a connection as it's returned to the pool via its [`PoolConnection`][super::PoolConnection]
+    /// guard, before it's put back on the idle queue. Return `false` to discard the connection
+    /// instead of recycling it.
     pub fn after_release<F>(mut self, callback: F) -> Self
     where
         F: Fn(&mut C) -> bool + 'static + Send + Sync,
@@ -151,6 +212,19 @@ impl<Rt: Runtime, C: Connection<Rt>> PoolOptions<Rt, C> {
         self
     }
 
+    /// Subscribe to pool lifecycle events (connection established/closed/checked out/checked
+    /// in, pool cleared/closed) for logging or metrics purposes.
+    ///
+    /// The callback runs inline on whatever task or thread triggered the event, so it should
+    /// be quick; hand off to a channel or async task for anything expensive.
+    pub fn on_event<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(PoolEvent<'_, Rt, C>) + 'static + Send + Sync,
+    {
+        self.on_event = Some(Box::new(callback));
+        self
+    }
+
     /// Creates a new pool from this configuration.
     ///
     /// Note that **this does not immediately connect to the database**;
@@ -189,15 +263,24 @@ impl<Rt: Runtime, C: Connection<Rt>> PoolOptions<Rt, C> {
 #[cfg(feature = "async")]
 impl<Rt: crate::Async, C: Connection<Rt>> PoolOptions<Rt, C> {
     /// Perform an action after connecting to the database.
+    ///
+    /// If the callback returns an error, the connection is discarded and the connect attempt
+    /// that produced it is treated as a failure (same as a failure from [`Connect::connect_with`]
+    /// itself).
     pub fn after_connect<F>(mut self, callback: F) -> Self
     where
         for<'c> F:
             Fn(&'c mut C) -> futures_util::BoxFuture<'c, crate::Result<()>> + Send + Sync + 'static,
     {
-        self.after_connect = Some(Box::new(callback));
+        self.after_connect_async = Some(Box::new(callback));
         self
     }
 
+    /// Perform a check on an idle connection before handing it out to an [`acquire()`][Pool::acquire()]
+    /// caller, after [`test_before_acquire`][Self::test_before_acquire] (if enabled).
+    ///
+    /// Returning `Ok(false)` or `Err(_)` discards the connection instead of checking it out;
+    /// `acquire()` then tries again with another idle connection or establishes a new one.
     pub fn before_acquire<F>(mut self, callback: F) -> Self
     where
         for<'c> F: Fn(&'c mut C) -> futures_util::BoxFuture<'c, crate::Result<bool>>
@@ -205,7 +288,7 @@ impl<Rt: crate::Async, C: Connection<Rt>> PoolOptions<Rt, C> {
             + Sync
             + 'static,
     {
-        self.before_acquire = Some(Box::new(callback));
+        self.before_acquire_async = Some(Box::new(callback));
         self
     }
 
@@ -235,11 +318,33 @@ impl<Rt: crate::Async, C: Connection<Rt>> PoolOptions<Rt, C> {
         self,
         options: <C as Connect<Rt>>::Options,
     ) -> crate::Result<Pool<Rt, C>> {
-        let mut shared = SharedPool::new(self, options);
+        let shared: Arc<SharedPool<Rt, C>> = SharedPool::new(self, options).into();
 
         shared.init_min_connections().await?;
+        shared.ensure_maintenance_spawned();
 
-        Ok(Pool { shared: shared.into() })
+        Ok(Pool { shared })
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl<Rt: crate::Blocking, C: Connection<Rt>> PoolOptions<Rt, C> {
+    /// Blocking equivalent of [`after_connect()`][Self::after_connect()].
+    pub fn after_connect<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&mut C) -> crate::Result<()> + Send + Sync + 'static,
+    {
+        self.after_connect_blocking = Some(Box::new(callback));
+        self
+    }
+
+    /// Blocking equivalent of [`before_acquire()`][Self::before_acquire()].
+    pub fn before_acquire<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&mut C) -> crate::Result<bool> + Send + Sync + 'static,
+    {
+        self.before_acquire_blocking = Some(Box::new(callback));
+        self
     }
 }
 
@@ -247,11 +352,14 @@ impl<Rt: Runtime, C: Connection<Rt>> Debug for PoolOptions<Rt, C> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_struct("PoolOptions")
             .field("max_connections", &self.max_connections)
+            .field("max_connecting", &self.max_connecting)
             .field("min_connections", &self.min_connections)
             .field("connect_timeout", &self.connect_timeout)
             .field("max_lifetime", &self.max_lifetime)
             .field("idle_timeout", &self.idle_timeout)
             .field("test_before_acquire", &self.test_before_acquire)
+            .field("statement_cache_capacity", &self.statement_cache_capacity)
+            .field("maintenance_interval", &self.maintenance_interval)
             .finish()
     }
 }
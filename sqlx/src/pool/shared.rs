@@ -0,0 +1,719 @@
+use crate::pool::connection::{Idle, Live};
+use crate::pool::events::{ConnectionCloseReason, PoolEvent};
+use crate::pool::options::PoolOptions;
+use crate::pool::state::PoolState;
+use crate::pool::statement_cache::SharedStatementCache;
+use crate::pool::wait_list::WaitList;
+use crate::{Connect, Connection, Runtime};
+use crossbeam_queue::ArrayQueue;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+/// The shared, reference-counted guts of a [`Pool`][super::Pool].
+///
+/// All the bookkeeping the pool needs lives here so that cloning a `Pool` handle is just an
+/// `Arc` clone.
+pub(crate) struct SharedPool<Rt: Runtime, C: Connection<Rt>> {
+    pub(crate) options: PoolOptions<Rt, C>,
+    connect_options: <C as Connect<Rt>>::Options,
+
+    idle: ArrayQueue<Idle<Rt, C>>,
+
+    /// Total number of connections currently owned by the pool, whether idle, checked out,
+    /// or in the process of being established. Never exceeds `options.max_connections`.
+    size: AtomicU32,
+
+    /// Number of connects currently in flight, distinct from `size` so that a burst of
+    /// acquirers doesn't open `max_connections` sockets to the database at once. Bounded by
+    /// `options.max_connecting` and decremented on both the success and failure paths.
+    connecting: AtomicU32,
+
+    pub(crate) waiters: WaitList,
+
+    // Cumulative statistics surfaced via `Pool::state()`. These only ever grow over the
+    // lifetime of the pool; `PoolState` is the place to look for point-in-time values.
+    acquire_count: AtomicU64,
+    acquire_timed_out_count: AtomicU64,
+    acquire_wait_time_nanos: AtomicU64,
+    connect_errors: AtomicU64,
+
+    /// Set by [`Pool::close()`][super::Pool::close()]. Once closed, `acquire()` fails fast
+    /// instead of waiting, and released connections are dropped instead of recycled.
+    is_closed: AtomicBool,
+
+    /// Bumped by [`Pool::clear()`][super::Pool::clear()]. Connections established in an older
+    /// generation are closed on release rather than reused, letting callers recycle the whole
+    /// pool (e.g. after a failover) without tearing down the `Pool` handle.
+    generation: AtomicU32,
+
+    /// Number of connections the pool is currently committed to handing out: either already
+    /// checked out via [`try_acquire_idle`]/`connect`/`connect_blocking`, or in the middle of
+    /// becoming so (idle validation or a fresh dial in progress). Incremented by
+    /// `begin_checkout` before that work starts and decremented by `release` or
+    /// `cancel_checkout`, whichever way it ends up resolving. Consulted by
+    /// `close`/`close_blocking` so a graceful shutdown can wait for in-flight work to actually
+    /// finish instead of returning the moment idle connections are drained and a dial it raced
+    /// against is still in flight.
+    outstanding_checkouts: AtomicU32,
+
+    /// `None` unless [`PoolOptions::statement_cache_capacity`] is nonzero.
+    statement_cache: Option<SharedStatementCache>,
+
+    /// Ensures the background maintenance monitor is only ever spawned once per pool, even
+    /// though both eager (`connect_with`) and lazy (first `acquire()`) construction try to do so.
+    maintenance_spawned: AtomicBool,
+}
+
+impl<Rt: Runtime, C: Connection<Rt>> SharedPool<Rt, C> {
+    pub(crate) fn new(options: PoolOptions<Rt, C>, connect_options: <C as Connect<Rt>>::Options) -> Self {
+        let capacity = options.max_connections as usize;
+        let statement_cache = SharedStatementCache::new(options.statement_cache_capacity);
+
+        Self {
+            idle: ArrayQueue::new(capacity.max(1)),
+            statement_cache,
+            size: AtomicU32::new(0),
+            connecting: AtomicU32::new(0),
+            waiters: WaitList::new(),
+            acquire_count: AtomicU64::new(0),
+            acquire_timed_out_count: AtomicU64::new(0),
+            acquire_wait_time_nanos: AtomicU64::new(0),
+            connect_errors: AtomicU64::new(0),
+            is_closed: AtomicBool::new(false),
+            generation: AtomicU32::new(0),
+            outstanding_checkouts: AtomicU32::new(0),
+            maintenance_spawned: AtomicBool::new(false),
+            options,
+            connect_options,
+        }
+    }
+
+    /// Take a snapshot of the pool's current and cumulative statistics.
+    pub(crate) fn state(&self) -> PoolState {
+        PoolState {
+            connections: self.size(),
+            idle_connections: self.num_idle() as u32,
+            pending_connections: self.connecting.load(Ordering::Acquire),
+            waiters: self.waiters.len(),
+            acquire_count: self.acquire_count.load(Ordering::Acquire),
+            acquire_timed_out_count: self.acquire_timed_out_count.load(Ordering::Acquire),
+            acquire_wait_time: Duration::from_nanos(
+                self.acquire_wait_time_nanos.load(Ordering::Acquire),
+            ),
+            connect_errors: self.connect_errors.load(Ordering::Acquire),
+        }
+    }
+
+    pub(crate) fn record_acquire(&self) {
+        self.acquire_count.fetch_add(1, Ordering::AcqRel);
+    }
+
+    pub(crate) fn record_acquire_timed_out(&self) {
+        self.acquire_timed_out_count.fetch_add(1, Ordering::AcqRel);
+    }
+
+    pub(crate) fn record_acquire_wait_time(&self, wait: Duration) {
+        self.acquire_wait_time_nanos
+            .fetch_add(wait.as_nanos() as u64, Ordering::AcqRel);
+    }
+
+    /// Fire a pool lifecycle event if a listener was installed via `PoolOptions::on_event`.
+    pub(crate) fn fire_event<'a>(&'a self, event: PoolEvent<'a, Rt, C>) {
+        if let Some(on_event) = &self.options.on_event {
+            on_event(event);
+        }
+    }
+
+    pub(crate) fn size(&self) -> u32 {
+        self.size.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        self.is_closed.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn generation(&self) -> u32 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    /// Mark the pool closed, wake every waiter so none are left blocked forever, and drop all
+    /// currently idle connections. Checked-out connections are closed as they're released
+    /// instead of being recycled. Does not itself wait for those checkouts to come back; see
+    /// `close`/`close_blocking` for the part of shutdown that does.
+    fn close_inner(&self) {
+        self.is_closed.store(true, Ordering::Release);
+        self.fire_event(PoolEvent::PoolClosed);
+
+        while let Some(idle) = self.idle.pop() {
+            self.fire_event(PoolEvent::ConnectionClosed {
+                connection: &idle.live.raw,
+                reason: ConnectionCloseReason::PoolClosed,
+            });
+            self.size.fetch_sub(1, Ordering::AcqRel);
+        }
+
+        // Wake every waiter so nobody is left parked on a pool that will never again produce
+        // a connection; each will re-check `is_closed` and return `Error::PoolClosed`.
+        self.waiters.notify_all();
+    }
+
+    /// Bump the pool generation and drop all idle connections. Unlike `close_inner`, the pool
+    /// remains open: new connections are established as usual, and outstanding checked-out
+    /// connections from the previous generation are closed on release instead of reused.
+    fn clear_inner(&self) {
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        self.fire_event(PoolEvent::PoolCleared);
+
+        while let Some(idle) = self.idle.pop() {
+            self.fire_event(PoolEvent::ConnectionClosed {
+                connection: &idle.live.raw,
+                reason: ConnectionCloseReason::Cleared,
+            });
+            self.size.fetch_sub(1, Ordering::AcqRel);
+        }
+
+        self.waiters.notify_all();
+    }
+
+    pub(crate) fn num_idle(&self) -> usize {
+        self.idle.len()
+    }
+
+    /// Record that the caller is now committed to handing a connection out: either one was
+    /// just popped off the idle queue and is being validated, or a dial is about to start for
+    /// a fresh one. Paired with exactly one of `release()` (the checkout completed and the
+    /// connection was later returned) or `cancel_checkout()` (the checkout fell through, e.g.
+    /// idle validation or the dial failed). Kept incremented across that whole span - not just
+    /// from the moment a connection handle actually exists - so `close()`/`close_blocking()`
+    /// can't return while a dial they raced against is still in flight.
+    pub(crate) fn begin_checkout(&self) {
+        self.outstanding_checkouts.fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// Undo a `begin_checkout()` whose checkout never actually completed. Wakes a waiter, same
+    /// as `release()`, since `close()`/`close_blocking()` may be blocked on this count.
+    pub(crate) fn cancel_checkout(&self) {
+        self.outstanding_checkouts.fetch_sub(1, Ordering::AcqRel);
+        self.waiters.notify(1);
+    }
+
+    /// Record that `sql` was just prepared against one of this pool's connections, so that
+    /// other connections can be primed with it later. This only happens on the application's
+    /// say-so, since the pool has no way to observe which statements a query actually prepared -
+    /// priming a freshly checked-out connection from what's already recorded, on the other hand,
+    /// is handled automatically by `validate_idle`/`validate_idle_blocking` and
+    /// `try_connect`/`try_connect_blocking`. No-op if the pool-wide statement cache is disabled.
+    pub(crate) fn record_prepared_statement(&self, sql: &str) {
+        if let Some(cache) = &self.statement_cache {
+            cache.record(sql);
+        }
+    }
+
+    /// The `n` hottest SQL texts across the whole pool, most-recently-prepared first. Empty if
+    /// the pool-wide statement cache is disabled.
+    pub(crate) fn hot_statements(&self, n: usize) -> Vec<Arc<str>> {
+        self.statement_cache.as_ref().map(|cache| cache.hottest(n)).unwrap_or_default()
+    }
+
+    /// Pop a connection off the idle queue, if one is available.
+    pub(crate) fn try_acquire_idle(&self) -> Option<Idle<Rt, C>> {
+        self.idle.pop()
+    }
+
+    /// Return a connection to the idle queue for reuse. If the queue is full, the connection
+    /// is dropped and `size` is decremented instead.
+    pub(crate) fn release(&self, mut live: Live<Rt, C>) {
+        // Every call here pairs with exactly one earlier `begin_checkout`, regardless of which
+        // branch below the connection takes on its way back in.
+        self.outstanding_checkouts.fetch_sub(1, Ordering::AcqRel);
+        self.fire_event(PoolEvent::ConnectionCheckedIn(&live.raw));
+
+        if self.is_closed() || live.generation != self.generation() {
+            let reason = if self.is_closed() {
+                ConnectionCloseReason::PoolClosed
+            } else {
+                ConnectionCloseReason::Cleared
+            };
+            self.fire_event(PoolEvent::ConnectionClosed { connection: &live.raw, reason });
+            drop(live);
+            self.size.fetch_sub(1, Ordering::AcqRel);
+            self.waiters.notify(1);
+            return;
+        }
+
+        if let Some(after_release) = &self.options.after_release {
+            if !after_release(&mut live.raw) {
+                self.fire_event(PoolEvent::ConnectionClosed {
+                    connection: &live.raw,
+                    reason: ConnectionCloseReason::Broken,
+                });
+                drop(live);
+                self.size.fetch_sub(1, Ordering::AcqRel);
+                self.waiters.notify(1);
+                return;
+            }
+        }
+
+        if let Err(idle) = self.idle.push(live.into_idle()) {
+            self.fire_event(PoolEvent::ConnectionClosed {
+                connection: &idle.live.raw,
+                reason: ConnectionCloseReason::Broken,
+            });
+            drop(idle);
+            self.size.fetch_sub(1, Ordering::AcqRel);
+        }
+
+        self.waiters.notify(1);
+    }
+
+    /// Reserve a slot to dial a new connection, respecting both `max_connections` and the
+    /// `max_connecting` throttle. Returns `false` if no slot is currently available, in which
+    /// case the caller should wait on `waiters` and retry.
+    fn try_begin_connect(&self) -> bool {
+        if self
+            .connecting
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |connecting| {
+                if connecting < self.options.max_connecting {
+                    Some(connecting + 1)
+                } else {
+                    None
+                }
+            })
+            .is_err()
+        {
+            return false;
+        }
+
+        if self
+            .size
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |size| {
+                if size < self.options.max_connections {
+                    Some(size + 1)
+                } else {
+                    None
+                }
+            })
+            .is_err()
+        {
+            // We reserved a connecting slot but the pool is already at `max_connections`;
+            // give the slot back immediately.
+            self.connecting.fetch_sub(1, Ordering::AcqRel);
+            return false;
+        }
+
+        true
+    }
+
+    /// Release the connecting slot and the `size` slot reserved by `try_begin_connect`. Used
+    /// on the failure path so a failed connect doesn't permanently consume either slot.
+    fn end_connect_failed(&self) {
+        self.connecting.fetch_sub(1, Ordering::AcqRel);
+        self.size.fetch_sub(1, Ordering::AcqRel);
+        // Somebody else may now be able to connect or may have been waiting on a free slot.
+        self.waiters.notify(1);
+    }
+
+    fn end_connect_succeeded(&self) {
+        self.connecting.fetch_sub(1, Ordering::AcqRel);
+        self.waiters.notify(1);
+    }
+
+    /// Close any idle connection that has exceeded `idle_timeout` or `max_lifetime`, returning
+    /// the rest to the idle queue. Called opportunistically from the background monitor; does
+    /// nothing if neither limit is configured.
+    fn reap_idle(&self) {
+        if self.options.idle_timeout.is_none() && self.options.max_lifetime.is_none() {
+            return;
+        }
+
+        let now = std::time::Instant::now();
+        let mut keep = Vec::with_capacity(self.idle.len());
+
+        while let Some(idle) = self.idle.pop() {
+            let reason = if self
+                .options
+                .max_lifetime
+                .is_some_and(|max| now.saturating_duration_since(idle.live.created_at) >= max)
+            {
+                Some(ConnectionCloseReason::MaxLifetime)
+            } else if self.options.idle_timeout.is_some_and(|timeout| idle.idle_for(now) >= timeout)
+            {
+                Some(ConnectionCloseReason::IdleTimeout)
+            } else {
+                None
+            };
+
+            match reason {
+                Some(reason) => {
+                    self.fire_event(PoolEvent::ConnectionClosed { connection: &idle.live.raw, reason });
+                    self.size.fetch_sub(1, Ordering::AcqRel);
+                }
+                None => keep.push(idle),
+            }
+        }
+
+        for idle in keep {
+            // The queue can't have shrunk since we drained it, so this can't fail.
+            let _ = self.idle.push(idle);
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<Rt: crate::Async, C: Connection<Rt>> SharedPool<Rt, C> {
+    /// Try to reserve a slot and dial one new connection without blocking. Returns `None` if
+    /// `max_connecting` or `max_connections` is currently saturated.
+    async fn try_connect(&self) -> Option<crate::Result<Live<Rt, C>>> {
+        if !self.try_begin_connect() {
+            return None;
+        }
+
+        Some(match C::connect_with(&self.connect_options).await {
+            Ok(mut raw) => {
+                if let Some(after_connect) = &self.options.after_connect_async {
+                    if let Err(e) = after_connect(&mut raw).await {
+                        self.end_connect_failed();
+                        self.connect_errors.fetch_add(1, Ordering::AcqRel);
+                        return Some(Err(e));
+                    }
+                }
+
+                self.prime_hot_statements(&mut raw).await;
+
+                self.end_connect_succeeded();
+                self.fire_event(PoolEvent::ConnectionEstablished(&raw));
+                Ok(Live::new(raw, self.generation()))
+            }
+            Err(e) => {
+                self.end_connect_failed();
+                self.connect_errors.fetch_add(1, Ordering::AcqRel);
+                Err(e)
+            }
+        })
+    }
+
+    /// Eagerly re-prepare this pool's hottest statements against `raw`, so a freshly checked-out
+    /// connection doesn't have to re-prepare each one lazily the first time it's used. Best
+    /// effort: a prepare failure here doesn't fail the checkout, since the worst case is just
+    /// falling back to that lazy re-preparation. No-op if the pool-wide statement cache is
+    /// disabled.
+    async fn prime_hot_statements(&self, raw: &mut C) {
+        let Some(cache) = &self.statement_cache else { return };
+
+        for sql in cache.hottest(usize::MAX) {
+            let _ = raw.prepare(&sql).await;
+        }
+    }
+
+    /// Check a connection popped off the idle queue before it's handed out to an `acquire()`
+    /// caller: ping it if [`PoolOptions::test_before_acquire`] is set, then run the
+    /// `before_acquire` hook if one is installed. Returns `None` if the connection failed
+    /// either check, in which case it has already been closed and `size` decremented; the
+    /// caller is expected to have called `begin_checkout()` beforehand and must call
+    /// `cancel_checkout()` on a `None` return before looping back around to try again.
+    pub(crate) async fn validate_idle(&self, idle: Idle<Rt, C>) -> Option<Live<Rt, C>> {
+        let mut live = idle.live;
+
+        if self.options.test_before_acquire && live.raw.ping().await.is_err() {
+            self.fire_event(PoolEvent::ConnectionClosed {
+                connection: &live.raw,
+                reason: ConnectionCloseReason::Broken,
+            });
+            self.size.fetch_sub(1, Ordering::AcqRel);
+            return None;
+        }
+
+        if let Some(before_acquire) = &self.options.before_acquire_async {
+            match before_acquire(&mut live.raw).await {
+                Ok(true) => {}
+                Ok(false) | Err(_) => {
+                    self.fire_event(PoolEvent::ConnectionClosed {
+                        connection: &live.raw,
+                        reason: ConnectionCloseReason::Broken,
+                    });
+                    self.size.fetch_sub(1, Ordering::AcqRel);
+                    return None;
+                }
+            }
+        }
+
+        self.prime_hot_statements(&mut live.raw).await;
+
+        Some(live)
+    }
+
+    /// Establish one new connection, honoring the `max_connecting` throttle. If the throttle
+    /// (or `max_connections`) is currently saturated, waits on `waiters` until a slot frees up,
+    /// failing with `Error::PoolTimedOut` if `deadline` elapses first.
+    pub(crate) async fn connect(&self, deadline: std::time::Instant) -> crate::Result<Live<Rt, C>> {
+        if self.is_closed() {
+            return Err(crate::Error::PoolClosed);
+        }
+
+        loop {
+            if let Some(result) = self.try_connect().await {
+                return result;
+            }
+
+            // Somebody else is already connecting or the pool is full; wait for them to
+            // finish (successfully or not) and try again, bailing out once `deadline` passes
+            // so a sustained connecting stampede can't hang the caller past `connect_timeout`.
+            if !self.waiters.wait_async_until::<Rt>(deadline).await {
+                return Err(crate::Error::PoolTimedOut);
+            }
+
+            if self.is_closed() {
+                return Err(crate::Error::PoolClosed);
+            }
+        }
+    }
+
+    /// Close the pool: fail fast on new acquires, wake blocked waiters, and drain idle
+    /// connections, then wait for every currently checked-out connection to be returned (and
+    /// closed, since `release` sees `is_closed()` and drops them instead of recycling).
+    pub(crate) async fn close(&self) {
+        self.close_inner();
+
+        while self.outstanding_checkouts.load(Ordering::Acquire) > 0 {
+            // Woken by `release()` or `cancel_checkout()`, whichever way each outstanding
+            // checkout ends up resolving.
+            self.waiters.wait_async().await;
+        }
+    }
+
+    /// Bump the pool generation and drop idle connections, without closing the pool itself.
+    pub(crate) async fn clear(&self) {
+        self.clear_inner();
+    }
+
+    /// Establish connections, eagerly, up to `min_connections` (or one connection if
+    /// `min_connections` is 0), for use right after the pool is constructed via
+    /// [`PoolOptions::connect`][crate::pool::PoolOptions::connect].
+    pub(crate) async fn init_min_connections(&self) -> crate::Result<()> {
+        let target = self.options.min_connections.max(1);
+
+        while self.size() < target {
+            let deadline = std::time::Instant::now() + self.options.connect_timeout;
+            // `release()` unconditionally pairs itself with a `begin_checkout()`, even though
+            // this connection was never actually checked out to anybody.
+            self.begin_checkout();
+            let live = match self.connect(deadline).await {
+                Ok(live) => live,
+                Err(e) => {
+                    self.cancel_checkout();
+                    return Err(e);
+                }
+            };
+            self.release(live);
+        }
+
+        Ok(())
+    }
+
+    /// One pass of background housekeeping: reap expired idle connections, then eagerly
+    /// re-establish connections to restore `min_connections` (best-effort; a failed connect or
+    /// a saturated `max_connecting` just means we try again on the next tick).
+    async fn run_maintenance(&self) {
+        self.reap_idle();
+
+        while self.size() < self.options.min_connections {
+            self.begin_checkout();
+            match self.try_connect().await {
+                Some(Ok(live)) => self.release(live),
+                Some(Err(_)) | None => {
+                    self.cancel_checkout();
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Spawn the background monitor task, if [`PoolOptions::maintenance_interval`] is set and a
+    /// monitor hasn't already been spawned for this pool. Holds only a [`Weak`] reference to the
+    /// pool, so the monitor exits on its own once every other handle is dropped instead of
+    /// keeping the pool (and its connections) alive forever.
+    pub(crate) fn ensure_maintenance_spawned(self: &Arc<Self>) {
+        let Some(interval) = self.options.maintenance_interval else {
+            return;
+        };
+
+        if self.maintenance_spawned.swap(true, Ordering::AcqRel) {
+            return;
+        }
+
+        // Weak, not a clone: the monitor must not be the reason a pool with no other handles
+        // left stays alive forever.
+        let pool: Weak<Self> = Arc::downgrade(self);
+        let _handle = Rt::spawn(async move {
+            loop {
+                Rt::sleep(interval).await;
+
+                let Some(pool) = pool.upgrade() else {
+                    break;
+                };
+
+                if pool.is_closed() {
+                    break;
+                }
+
+                pool.run_maintenance().await;
+            }
+        });
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl<Rt: crate::Blocking, C: Connection<Rt>> SharedPool<Rt, C> {
+    pub(crate) fn close_blocking(&self) {
+        self.close_inner();
+
+        while self.outstanding_checkouts.load(Ordering::Acquire) > 0 {
+            // Woken by `release()` or `cancel_checkout()`, whichever way each outstanding
+            // checkout ends up resolving.
+            self.waiters.wait_blocking_forever();
+        }
+    }
+
+    pub(crate) fn clear_blocking(&self) {
+        self.clear_inner();
+    }
+
+    /// Blocking equivalent of `try_connect`.
+    fn try_connect_blocking(&self) -> Option<crate::Result<Live<Rt, C>>> {
+        if !self.try_begin_connect() {
+            return None;
+        }
+
+        Some(match C::connect_with(&self.connect_options) {
+            Ok(mut raw) => {
+                if let Some(after_connect) = &self.options.after_connect_blocking {
+                    if let Err(e) = after_connect(&mut raw) {
+                        self.end_connect_failed();
+                        self.connect_errors.fetch_add(1, Ordering::AcqRel);
+                        return Some(Err(e));
+                    }
+                }
+
+                self.prime_hot_statements_blocking(&mut raw);
+
+                self.end_connect_succeeded();
+                self.fire_event(PoolEvent::ConnectionEstablished(&raw));
+                Ok(Live::new(raw, self.generation()))
+            }
+            Err(e) => {
+                self.end_connect_failed();
+                self.connect_errors.fetch_add(1, Ordering::AcqRel);
+                Err(e)
+            }
+        })
+    }
+
+    /// Blocking equivalent of `prime_hot_statements`.
+    fn prime_hot_statements_blocking(&self, raw: &mut C) {
+        let Some(cache) = &self.statement_cache else { return };
+
+        for sql in cache.hottest(usize::MAX) {
+            let _ = raw.prepare(&sql);
+        }
+    }
+
+    /// Blocking equivalent of `validate_idle`.
+    pub(crate) fn validate_idle_blocking(&self, idle: Idle<Rt, C>) -> Option<Live<Rt, C>> {
+        let mut live = idle.live;
+
+        if self.options.test_before_acquire && live.raw.ping().is_err() {
+            self.fire_event(PoolEvent::ConnectionClosed {
+                connection: &live.raw,
+                reason: ConnectionCloseReason::Broken,
+            });
+            self.size.fetch_sub(1, Ordering::AcqRel);
+            return None;
+        }
+
+        if let Some(before_acquire) = &self.options.before_acquire_blocking {
+            match before_acquire(&mut live.raw) {
+                Ok(true) => {}
+                Ok(false) | Err(_) => {
+                    self.fire_event(PoolEvent::ConnectionClosed {
+                        connection: &live.raw,
+                        reason: ConnectionCloseReason::Broken,
+                    });
+                    self.size.fetch_sub(1, Ordering::AcqRel);
+                    return None;
+                }
+            }
+        }
+
+        self.prime_hot_statements_blocking(&mut live.raw);
+
+        Some(live)
+    }
+
+    /// Blocking equivalent of `connect`, with a deadline of `connect_timeout` from the call.
+    pub(crate) fn connect_blocking(&self) -> crate::Result<Live<Rt, C>> {
+        if self.is_closed() {
+            return Err(crate::Error::PoolClosed);
+        }
+
+        let deadline = std::time::Instant::now() + self.options.connect_timeout;
+
+        loop {
+            if let Some(result) = self.try_connect_blocking() {
+                return result;
+            }
+
+            if !self.waiters.wait_blocking(deadline) {
+                return Err(crate::Error::PoolTimedOut);
+            }
+
+            if self.is_closed() {
+                return Err(crate::Error::PoolClosed);
+            }
+        }
+    }
+
+    fn run_maintenance_blocking(&self) {
+        self.reap_idle();
+
+        while self.size() < self.options.min_connections {
+            self.begin_checkout();
+            match self.try_connect_blocking() {
+                Some(Ok(live)) => self.release(live),
+                Some(Err(_)) | None => {
+                    self.cancel_checkout();
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Blocking equivalent of `ensure_maintenance_spawned`, driving the monitor on its own
+    /// thread instead of a spawned task.
+    pub(crate) fn ensure_maintenance_spawned_blocking(self: &Arc<Self>) {
+        let Some(interval) = self.options.maintenance_interval else {
+            return;
+        };
+
+        if self.maintenance_spawned.swap(true, Ordering::AcqRel) {
+            return;
+        }
+
+        // Weak, not a clone: the monitor must not be the reason a pool with no other handles
+        // left stays alive forever.
+        let pool: Weak<Self> = Arc::downgrade(self);
+        std::thread::spawn(move || loop {
+            std::thread::sleep(interval);
+
+            let Some(pool) = pool.upgrade() else {
+                break;
+            };
+
+            if pool.is_closed() {
+                break;
+            }
+
+            pool.run_maintenance_blocking();
+        });
+    }
+}
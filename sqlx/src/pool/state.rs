@@ -0,0 +1,42 @@
+use std::time::Duration;
+
+/// A snapshot of a [`Pool`][super::Pool]'s live statistics, taken at the time
+/// [`Pool::state()`][super::Pool::state()] was called.
+///
+/// Intended to be exported to whatever metrics system the caller already uses; `sqlx` itself
+/// does not interpret these numbers.
+#[derive(Debug, Clone)]
+pub struct PoolState {
+    /// The number of connections currently owned by the pool: idle, checked out, or still in
+    /// the process of being established. The in-flight portion is also broken out separately as
+    /// [`pending_connections`][Self::pending_connections], so `connections - idle_connections`
+    /// overstates the number actually checked out by however many connects are still pending.
+    pub connections: u32,
+
+    /// The number of connections currently sitting idle in the pool, immediately available to
+    /// be handed out by [`Pool::acquire()`][super::Pool::acquire()].
+    pub idle_connections: u32,
+
+    /// The number of connections currently in the process of being established, i.e. bounded
+    /// by [`PoolOptions::max_connecting`][super::PoolOptions::max_connecting].
+    pub pending_connections: u32,
+
+    /// The number of tasks currently blocked waiting on [`Pool::acquire()`][super::Pool::acquire()],
+    /// plus any blocked in [`Pool::close()`][super::Pool::close()] waiting for the pool to drain.
+    pub waiters: usize,
+
+    /// The cumulative number of times [`Pool::acquire()`][super::Pool::acquire()] has been
+    /// called on an open pool, successfully or not, since the pool was created. Calls made
+    /// after the pool was closed are not counted.
+    pub acquire_count: u64,
+
+    /// The cumulative number of times an acquire has failed with a timeout.
+    pub acquire_timed_out_count: u64,
+
+    /// The cumulative amount of time spent waiting for a connection across all successful
+    /// acquires. Time spent in acquires that failed is not included.
+    pub acquire_wait_time: Duration,
+
+    /// The cumulative number of errors encountered while establishing new connections.
+    pub connect_errors: u64,
+}
@@ -0,0 +1,118 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// Tracks which SQL statements have recently been prepared against *any* connection in the
+/// pool, independent of which specific connection prepared them.
+///
+/// Per the `mysql` crate's pool-level `use_cache` behavior: a statement prepared on one pooled
+/// connection is otherwise re-prepared from scratch the next time a *different* connection is
+/// checked out for the same query. This doesn't cache statement handles directly (those aren't
+/// portable between sessions) - it only remembers which SQL texts are "hot" across the pool, so
+/// that a freshly checked-out connection can eagerly re-prepare them instead of waiting to hit
+/// each one again on first use.
+///
+/// Entries are evicted least-recently-used once `capacity` is exceeded.
+pub(crate) struct SharedStatementCache {
+    capacity: usize,
+    entries: Mutex<VecDeque<Arc<str>>>,
+}
+
+impl SharedStatementCache {
+    /// Returns `None` if `capacity` is 0, meaning the pool-wide cache is disabled.
+    pub(crate) fn new(capacity: usize) -> Option<Self> {
+        if capacity == 0 {
+            return None;
+        }
+
+        Some(Self { capacity, entries: Mutex::new(VecDeque::with_capacity(capacity)) })
+    }
+
+    /// Record that `sql` was just prepared against some connection in the pool, marking it
+    /// most-recently-used.
+    pub(crate) fn record(&self, sql: &str) {
+        let mut entries = self.entries.lock().unwrap();
+
+        if let Some(pos) = entries.iter().position(|cached| &**cached == sql) {
+            let entry = entries.remove(pos).expect("position came from this deque");
+            entries.push_back(entry);
+            return;
+        }
+
+        if entries.len() >= self.capacity {
+            entries.pop_front();
+        }
+
+        entries.push_back(Arc::from(sql));
+    }
+
+    /// The `n` most-recently-prepared SQL texts, most-recent first.
+    pub(crate) fn hottest(&self, n: usize) -> Vec<Arc<str>> {
+        self.entries.lock().unwrap().iter().rev().take(n).cloned().collect()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SharedStatementCache;
+
+    #[test]
+    fn new_returns_none_for_zero_capacity() {
+        assert!(SharedStatementCache::new(0).is_none());
+    }
+
+    #[test]
+    fn hottest_returns_most_recently_recorded_first() {
+        let cache = SharedStatementCache::new(3).unwrap();
+
+        cache.record("select 1");
+        cache.record("select 2");
+        cache.record("select 3");
+
+        assert_eq!(
+            cache.hottest(3),
+            vec!["select 3".into(), "select 2".into(), "select 1".into()]
+        );
+    }
+
+    #[test]
+    fn hottest_respects_n() {
+        let cache = SharedStatementCache::new(3).unwrap();
+
+        cache.record("select 1");
+        cache.record("select 2");
+        cache.record("select 3");
+
+        assert_eq!(cache.hottest(2), vec!["select 3".into(), "select 2".into()]);
+    }
+
+    #[test]
+    fn evicts_least_recently_used_once_over_capacity() {
+        let cache = SharedStatementCache::new(2).unwrap();
+
+        cache.record("select 1");
+        cache.record("select 2");
+        // Capacity is 2, so this evicts "select 1", the least recently used entry.
+        cache.record("select 3");
+
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.hottest(2), vec!["select 3".into(), "select 2".into()]);
+    }
+
+    #[test]
+    fn re_recording_an_entry_moves_it_to_most_recently_used() {
+        let cache = SharedStatementCache::new(2).unwrap();
+
+        cache.record("select 1");
+        cache.record("select 2");
+        // Re-recording "select 1" should make it the MRU entry, protecting it from eviction.
+        cache.record("select 1");
+        cache.record("select 3");
+
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.hottest(2), vec!["select 3".into(), "select 1".into()]);
+    }
+}
@@ -0,0 +1,293 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "blocking")]
+use std::sync::Condvar;
+
+#[cfg(feature = "async")]
+use std::task::Waker;
+
+/// Tracks tasks/threads that are blocked in [`acquire`][super::Pool::acquire] waiting for a
+/// connection to become available, so that [`SharedPool`][super::shared::SharedPool] can wake
+/// them up as connections are released, established, or the pool is closed.
+///
+/// This is deliberately a plain FIFO queue of wakers rather than a counting semaphore: we need
+/// to be able to wake a *specific* number of waiters (one per freed slot) as well as wake
+/// *every* waiter at once when the pool is closed.
+pub(crate) struct WaitList {
+    waiters: Mutex<VecDeque<Arc<Waiter>>>,
+}
+
+struct Waiter {
+    woken: Mutex<bool>,
+    #[cfg(feature = "async")]
+    waker: Mutex<Option<Waker>>,
+    #[cfg(feature = "blocking")]
+    condvar: Condvar,
+}
+
+/// An RAII handle for a single [`Waiter`] registered with a [`WaitList`]. Ensures the waiter is
+/// removed from the list once the caller is done waiting on it, whichever way that happens
+/// (woken, timed out, or the future/thread simply gave up).
+struct Registration<'a> {
+    list: &'a WaitList,
+    waiter: Arc<Waiter>,
+}
+
+impl Drop for Registration<'_> {
+    fn drop(&mut self) {
+        self.list.remove(&self.waiter);
+    }
+}
+
+impl WaitList {
+    pub(crate) fn new() -> Self {
+        Self { waiters: Mutex::new(VecDeque::new()) }
+    }
+
+    /// The number of tasks/threads currently waiting for a connection.
+    pub(crate) fn len(&self) -> usize {
+        self.waiters.lock().unwrap().len()
+    }
+
+    /// Register a new waiter and return an RAII handle for it. The handle removes the waiter
+    /// from the queue on drop unless it was already popped by `notify`/`notify_all`, so a
+    /// caller that stops waiting without being woken (timed out, or bailed out of a `select!`)
+    /// never leaves a dead entry behind for `notify`/`notify_all` to trip over or `len()` to
+    /// keep counting.
+    fn register(&self) -> Registration<'_> {
+        let waiter = Arc::new(Waiter {
+            woken: Mutex::new(false),
+            #[cfg(feature = "async")]
+            waker: Mutex::new(None),
+            #[cfg(feature = "blocking")]
+            condvar: Condvar::new(),
+        });
+
+        self.waiters.lock().unwrap().push_back(waiter.clone());
+
+        Registration { list: self, waiter }
+    }
+
+    /// Remove `waiter` from the queue if it's still there. No-op if it was already popped by
+    /// `notify`/`notify_all`.
+    fn remove(&self, waiter: &Arc<Waiter>) {
+        let mut waiters = self.waiters.lock().unwrap();
+        if let Some(pos) = waiters.iter().position(|w| Arc::ptr_eq(w, waiter)) {
+            waiters.remove(pos);
+        }
+    }
+
+    /// Wake up to `n` waiters, oldest first. Returns the number actually woken.
+    pub(crate) fn notify(&self, n: usize) -> usize {
+        let mut waiters = self.waiters.lock().unwrap();
+        let mut woken = 0;
+
+        for _ in 0..n {
+            match waiters.pop_front() {
+                Some(waiter) => {
+                    waiter.wake();
+                    woken += 1;
+                }
+                None => break,
+            }
+        }
+
+        woken
+    }
+
+    /// Wake every currently-registered waiter. Used when the pool is closed so that no
+    /// acquirer is left blocked forever.
+    pub(crate) fn notify_all(&self) {
+        let mut waiters = self.waiters.lock().unwrap();
+
+        for waiter in waiters.drain(..) {
+            waiter.wake();
+        }
+    }
+}
+
+impl Waiter {
+    fn wake(&self) {
+        *self.woken.lock().unwrap() = true;
+
+        #[cfg(feature = "async")]
+        if let Some(waker) = self.waker.lock().unwrap().take() {
+            waker.wake();
+        }
+
+        #[cfg(feature = "blocking")]
+        self.condvar.notify_one();
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl WaitList {
+    /// Block the current thread until woken or `deadline` elapses.
+    pub(crate) fn wait_blocking(&self, deadline: std::time::Instant) -> bool {
+        let registration = self.register();
+        let waiter = &registration.waiter;
+
+        let mut woken = waiter.woken.lock().unwrap();
+        while !*woken {
+            let timeout = deadline.saturating_duration_since(std::time::Instant::now());
+            if timeout.is_zero() {
+                break;
+            }
+
+            let (guard, result) = waiter.condvar.wait_timeout(woken, timeout).unwrap();
+            woken = guard;
+            if result.timed_out() {
+                break;
+            }
+        }
+
+        *woken
+    }
+
+    /// Block the current thread until woken, with no deadline. Used for graceful shutdown,
+    /// where we genuinely want to wait as long as it takes rather than time out.
+    pub(crate) fn wait_blocking_forever(&self) {
+        let registration = self.register();
+        let waiter = &registration.waiter;
+
+        let mut woken = waiter.woken.lock().unwrap();
+        while !*woken {
+            woken = waiter.condvar.wait(woken).unwrap();
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl WaitList {
+    /// Asynchronously wait until some other task calls `notify`/`notify_all`.
+    pub(crate) async fn wait_async(&self) {
+        let registration = self.register();
+        Self::poll_woken(&registration.waiter).await
+    }
+
+    /// Asynchronously wait until some other task calls `notify`/`notify_all`, or `deadline`
+    /// elapses. Returns `true` if woken, `false` on timeout.
+    pub(crate) async fn wait_async_until<Rt: crate::Async>(&self, deadline: std::time::Instant) -> bool {
+        let registration = self.register();
+        let waiter = &registration.waiter;
+
+        let timeout = deadline.saturating_duration_since(std::time::Instant::now());
+        if timeout.is_zero() {
+            return *waiter.woken.lock().unwrap();
+        }
+
+        futures_util::future::select(
+            Box::pin(Self::poll_woken(waiter)),
+            Box::pin(Rt::sleep(timeout)),
+        )
+        .await;
+
+        *waiter.woken.lock().unwrap()
+    }
+
+    fn poll_woken(waiter: &Arc<Waiter>) -> impl std::future::Future<Output = ()> + '_ {
+        futures_util::future::poll_fn(move |cx| {
+            if *waiter.woken.lock().unwrap() {
+                return std::task::Poll::Ready(());
+            }
+
+            *waiter.waker.lock().unwrap() = Some(cx.waker().clone());
+
+            // Re-check after registering the waker to avoid missing a wakeup that raced us.
+            if *waiter.woken.lock().unwrap() {
+                std::task::Poll::Ready(())
+            } else {
+                std::task::Poll::Pending
+            }
+        })
+    }
+}
+
+#[cfg(all(test, feature = "blocking"))]
+mod tests {
+    use super::WaitList;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn wait_blocking_times_out_without_a_notify() {
+        let waiters = WaitList::new();
+
+        let woken = waiters.wait_blocking(Instant::now() + Duration::from_millis(50));
+
+        assert!(!woken, "wait_blocking should report a timeout, not a spurious wakeup");
+    }
+
+    #[test]
+    fn wait_blocking_wakes_up_on_notify() {
+        let waiters = std::sync::Arc::new(WaitList::new());
+        let notifier = waiters.clone();
+
+        let handle = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            notifier.notify(1);
+        });
+
+        let woken = waiters.wait_blocking(Instant::now() + Duration::from_secs(5));
+        handle.join().unwrap();
+
+        assert!(woken, "wait_blocking should return promptly once notified, well before its deadline");
+    }
+
+    #[test]
+    fn wait_blocking_forever_waits_for_an_explicit_notify() {
+        let waiters = std::sync::Arc::new(WaitList::new());
+        let notifier = waiters.clone();
+
+        let handle = std::thread::spawn(move || {
+            // Give the waiting thread a head start so we actually exercise the blocking path
+            // instead of racing a wakeup in before `wait_blocking_forever` registers.
+            std::thread::sleep(Duration::from_millis(20));
+            notifier.notify(1);
+        });
+
+        let started = Instant::now();
+        waiters.wait_blocking_forever();
+        handle.join().unwrap();
+
+        assert!(
+            started.elapsed() >= Duration::from_millis(20),
+            "wait_blocking_forever should not return before the notify that wakes it"
+        );
+    }
+
+    #[test]
+    fn wait_blocking_removes_itself_from_the_queue_on_timeout() {
+        let waiters = WaitList::new();
+
+        let woken = waiters.wait_blocking(Instant::now() + Duration::from_millis(20));
+
+        assert!(!woken);
+        assert_eq!(waiters.len(), 0, "a timed-out waiter must not be left behind in the queue");
+    }
+
+    #[test]
+    fn notify_wakes_at_most_n_oldest_waiters() {
+        let waiters = std::sync::Arc::new(WaitList::new());
+
+        let mut handles = Vec::new();
+        for _ in 0..3 {
+            let waiters = waiters.clone();
+            handles.push(std::thread::spawn(move || {
+                waiters.wait_blocking(Instant::now() + Duration::from_secs(5))
+            }));
+        }
+
+        // Give all three threads a chance to register before we notify.
+        while waiters.len() < 3 {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+
+        assert_eq!(waiters.notify(2), 2);
+        // Release the thread left behind instead of letting it ride out its 5s deadline.
+        assert_eq!(waiters.notify(1), 1);
+
+        let results: Vec<bool> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        assert!(results.iter().all(|woken| *woken));
+    }
+}